@@ -0,0 +1,411 @@
+// Recursive directory index backed by a local SQLite database.
+//
+// `scan_dir` walks a tree once and persists one row per file/folder so later
+// searches query the index instead of walking the tree again; each hit is
+// still re-stat'ed from disk before it is returned. Re-scans compare mtime+size
+// per path and only re-hash entries that actually changed.
+
+use crate::scope::{self, ScopeState};
+use crate::{stat_entry, system_time_to_secs, FileEntry};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+pub struct IndexState(Mutex<rusqlite::Connection>);
+
+impl IndexState {
+    pub fn new(conn: rusqlite::Connection) -> Self {
+        Self(Mutex::new(conn))
+    }
+}
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS entries (
+        id           INTEGER PRIMARY KEY AUTOINCREMENT,
+        parent_id    INTEGER REFERENCES entries(id) ON DELETE CASCADE,
+        path         TEXT NOT NULL UNIQUE,
+        name         TEXT NOT NULL,
+        size         INTEGER NOT NULL,
+        mtime        INTEGER NOT NULL,
+        is_dir       INTEGER NOT NULL,
+        content_hash TEXT
+    );
+    CREATE INDEX IF NOT EXISTS idx_entries_name ON entries(name);
+";
+
+// Opens (creating if needed) the index database under the app data directory.
+// Called once from `run`'s `setup` hook, before any command can run.
+pub fn init(app: &AppHandle) -> Result<rusqlite::Connection, String> {
+    let data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    std::fs::create_dir_all(&data_dir)
+        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+
+    let conn = rusqlite::Connection::open(data_dir.join("index.db"))
+        .map_err(|e| format!("Failed to open index database: {}", e))?;
+    // Off by default per connection in SQLite; without it `ON DELETE CASCADE`
+    // on `entries.parent_id` is inert and a deleted directory row would leave
+    // its children behind.
+    conn.execute_batch("PRAGMA foreign_keys = ON;")
+        .map_err(|e| format!("Failed to enable foreign key support: {}", e))?;
+    conn.execute_batch(SCHEMA)
+        .map_err(|e| format!("Failed to initialize index schema: {}", e))?;
+    Ok(conn)
+}
+
+// Escapes `%`/`_`/`\` so a path can be safely embedded in a `LIKE ... ESCAPE
+// '\'` pattern.
+fn escape_like(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+// Below this size it's cheaper to just hash the whole file than to seek.
+const FULL_HASH_THRESHOLD: u64 = 1024 * 1024; // 1 MiB
+const SAMPLE_WINDOW: u64 = 16 * 1024; // 16 KiB
+
+// Hashes a whole file with BLAKE3.
+fn full_hash(path: &Path) -> Option<String> {
+    let mut file = std::fs::File::open(path).ok()?;
+    let mut hasher = blake3::Hasher::new();
+    std::io::copy(&mut file, &mut hasher).ok()?;
+    Some(hasher.finalize().to_hex().to_string())
+}
+
+// Cheap duplicate-detection digest: small files are hashed in full, large
+// ones are identified by sampling three fixed, length-anchored windows
+// (start, exact middle, end) plus the file length. Two distinct files of the
+// same length might collide here; `find_duplicates` confirms collisions with
+// `full_hash` before reporting them.
+fn partial_hash(path: &Path, size: u64) -> Option<String> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    if size <= FULL_HASH_THRESHOLD {
+        return full_hash(path);
+    }
+
+    let mut file = std::fs::File::open(path).ok()?;
+    let mut hasher = blake3::Hasher::new();
+    let mut buf = vec![0u8; SAMPLE_WINDOW as usize];
+
+    for offset in [0, size / 2, size - SAMPLE_WINDOW] {
+        file.seek(SeekFrom::Start(offset)).ok()?;
+        let read = file.read(&mut buf).ok()?;
+        hasher.update(&buf[..read]);
+    }
+    hasher.update(&size.to_le_bytes());
+
+    Some(hasher.finalize().to_hex().to_string())
+}
+
+#[tauri::command]
+pub fn scan_dir(
+    path: String,
+    state: tauri::State<IndexState>,
+    scope: tauri::State<ScopeState>,
+) -> Result<usize, String> {
+    let conn = state.0.lock().map_err(|_| "Index database lock poisoned".to_string())?;
+    scan_dir_inner(&path, &conn, &scope)
+}
+
+fn scan_dir_inner(path: &str, conn: &rusqlite::Connection, scope: &ScopeState) -> Result<usize, String> {
+    let root = scope::ensure_in_scope(scope, path).map_err(|e| e.to_string())?;
+    // Maps an already-indexed directory's path to its row id, so children can
+    // record the right `parent_id` as the walk descends.
+    let mut ids: HashMap<PathBuf, i64> = HashMap::new();
+    // Every path this walk actually saw, so `reconcile_deletions` can tell a
+    // row that's still there from one left behind by a rename or delete.
+    let mut touched: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let mut indexed = 0usize;
+
+    for walk_entry in walkdir::WalkDir::new(&root).into_iter().flatten() {
+        let entry_path = walk_entry.path();
+        let metadata = match walk_entry.metadata() {
+            Ok(m) => m,
+            Err(_) => continue,
+        };
+        touched.insert(entry_path.to_string_lossy().to_string());
+
+        let is_dir = metadata.is_dir();
+        let size = metadata.len();
+        let mtime = system_time_to_secs(metadata.modified()) as i64;
+        let parent_id = entry_path.parent().and_then(|p| ids.get(p)).copied();
+
+        let existing: Option<(i64, i64, i64, Option<String>)> = conn
+            .query_row(
+                "SELECT id, size, mtime, content_hash FROM entries WHERE path = ?1",
+                [entry_path.to_string_lossy().to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .ok();
+
+        let content_hash = match &existing {
+            // Unchanged since last scan: keep the hash we already paid for.
+            Some((_, old_size, old_mtime, old_hash)) if *old_size == size as i64 && *old_mtime == mtime => {
+                old_hash.clone()
+            }
+            _ => {
+                if is_dir {
+                    None
+                } else {
+                    partial_hash(entry_path, size)
+                }
+            }
+        };
+
+        let id = if let Some((id, _, _, _)) = existing {
+            conn.execute(
+                "UPDATE entries SET parent_id = ?1, size = ?2, mtime = ?3, is_dir = ?4, content_hash = ?5 WHERE id = ?6",
+                rusqlite::params![parent_id, size as i64, mtime, is_dir, content_hash, id],
+            )
+            .map_err(|e| format!("Failed to update index row: {}", e))?;
+            id
+        } else {
+            let name = entry_path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| entry_path.to_string_lossy().to_string());
+            conn.execute(
+                "INSERT INTO entries (parent_id, path, name, size, mtime, is_dir, content_hash)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                rusqlite::params![
+                    parent_id,
+                    entry_path.to_string_lossy().to_string(),
+                    name,
+                    size as i64,
+                    mtime,
+                    is_dir,
+                    content_hash
+                ],
+            )
+            .map_err(|e| format!("Failed to insert index row: {}", e))?;
+            conn.last_insert_rowid()
+        };
+
+        if is_dir {
+            ids.insert(entry_path.to_path_buf(), id);
+        }
+        indexed += 1;
+    }
+
+    reconcile_deletions(conn, &root, &touched)?;
+
+    Ok(indexed)
+}
+
+// Removes rows under `root` that this scan didn't see again: files or
+// folders deleted since the last scan, and the stale old-path row a rename
+// leaves behind. Runs after every scan so re-scanning a tree with churn
+// (temp files, build output, renames) doesn't grow the DB forever.
+fn reconcile_deletions(
+    conn: &rusqlite::Connection,
+    root: &Path,
+    touched: &std::collections::HashSet<String>,
+) -> Result<(), String> {
+    let root_str = root.to_string_lossy().to_string();
+    let child_pattern = format!("{}{}%", escape_like(&root_str), std::path::MAIN_SEPARATOR);
+
+    let mut stmt = conn
+        .prepare("SELECT id, path FROM entries WHERE path = ?1 OR path LIKE ?2 ESCAPE '\\'")
+        .map_err(|e| format!("Failed to prepare reconciliation query: {}", e))?;
+    let stale_ids: Vec<i64> = stmt
+        .query_map(rusqlite::params![root_str, child_pattern], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(|e| format!("Failed to scan index for stale rows: {}", e))?
+        .flatten()
+        .filter(|(_, path)| !touched.contains(path))
+        .map(|(id, _)| id)
+        .collect();
+
+    for id in stale_ids {
+        // A cascade from an earlier deletion in this same pass may already
+        // have removed a child row; deleting it again is a harmless no-op.
+        conn.execute("DELETE FROM entries WHERE id = ?1", [id])
+            .map_err(|e| format!("Failed to delete stale index row: {}", e))?;
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn search_index(
+    query: String,
+    state: tauri::State<IndexState>,
+    scope: tauri::State<ScopeState>,
+) -> Result<Vec<FileEntry>, String> {
+    let conn = state.0.lock().map_err(|_| "Index database lock poisoned".to_string())?;
+    search_index_inner(&query, &conn, &scope)
+}
+
+fn search_index_inner(
+    query: &str,
+    conn: &rusqlite::Connection,
+    scope: &ScopeState,
+) -> Result<Vec<FileEntry>, String> {
+    let pattern = format!("%{}%", escape_like(query));
+
+    let mut stmt = conn
+        .prepare("SELECT path FROM entries WHERE name LIKE ?1 ESCAPE '\\' ORDER BY name COLLATE NOCASE")
+        .map_err(|e| format!("Failed to prepare search query: {}", e))?;
+    let paths = stmt
+        .query_map([pattern], |row| row.get::<_, String>(0))
+        .map_err(|e| format!("Failed to run search query: {}", e))?;
+
+    // Re-stat from disk so results reflect current metadata rather than a
+    // potentially stale index row; entries removed since the last scan, or
+    // that fall outside the current scope allowlist, are silently dropped
+    // instead of being reported as hits.
+    Ok(paths
+        .flatten()
+        .map(PathBuf::from)
+        .filter(|p| scope::path_in_scope(scope, p))
+        .filter_map(|p| stat_entry(&p))
+        .collect())
+}
+
+// Groups indexed files by partial hash, then confirms each collision group
+// with a full-file hash so only genuine duplicates are reported.
+#[tauri::command]
+pub fn find_duplicates(
+    state: tauri::State<IndexState>,
+    scope: tauri::State<ScopeState>,
+) -> Result<Vec<Vec<FileEntry>>, String> {
+    let conn = state.0.lock().map_err(|_| "Index database lock poisoned".to_string())?;
+    find_duplicates_inner(&conn, &scope)
+}
+
+fn find_duplicates_inner(conn: &rusqlite::Connection, scope: &ScopeState) -> Result<Vec<Vec<FileEntry>>, String> {
+    let mut stmt = conn
+        .prepare("SELECT content_hash, path FROM entries WHERE is_dir = 0 AND content_hash IS NOT NULL")
+        .map_err(|e| format!("Failed to prepare duplicate scan: {}", e))?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+        .map_err(|e| format!("Failed to scan index for duplicates: {}", e))?;
+
+    // Drop rows outside the current scope allowlist before any hashing, so
+    // neither the digest nor the existence of out-of-scope files ever reaches
+    // the caller.
+    let mut by_partial_hash: HashMap<String, Vec<String>> = HashMap::new();
+    for (hash, path) in rows.flatten() {
+        if scope::path_in_scope(scope, Path::new(&path)) {
+            by_partial_hash.entry(hash).or_default().push(path);
+        }
+    }
+
+    let mut duplicates = Vec::new();
+    for paths in by_partial_hash.into_values() {
+        if paths.len() < 2 {
+            continue;
+        }
+
+        // Only within this collision group, confirm with a full-file hash.
+        let mut by_full_hash: HashMap<String, Vec<String>> = HashMap::new();
+        for path in paths {
+            if let Some(hash) = full_hash(Path::new(&path)) {
+                by_full_hash.entry(hash).or_default().push(path);
+            }
+        }
+
+        for confirmed in by_full_hash.into_values() {
+            if confirmed.len() > 1 {
+                duplicates.push(confirmed.iter().filter_map(|p| stat_entry(Path::new(p))).collect());
+            }
+        }
+    }
+
+    Ok(duplicates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("sprintloop_indexing_test_{}", name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).expect("create temp test root");
+        dir
+    }
+
+    fn open_test_db() -> rusqlite::Connection {
+        let conn = rusqlite::Connection::open_in_memory().expect("open in-memory db");
+        conn.execute_batch("PRAGMA foreign_keys = ON;").expect("enable foreign keys");
+        conn.execute_batch(SCHEMA).expect("init schema");
+        conn
+    }
+
+    #[test]
+    fn scan_dir_rejects_a_path_outside_the_allowed_roots() {
+        let allowed = temp_root("scan_allowed");
+        let outside = temp_root("scan_outside");
+        std::fs::write(outside.join("secret.txt"), b"nope").unwrap();
+
+        let conn = open_test_db();
+        let scope = ScopeState::new(vec![allowed]);
+
+        let result = scan_dir_inner(outside.to_str().unwrap(), &conn, &scope);
+
+        assert!(result.is_err());
+        let indexed: i64 = conn
+            .query_row("SELECT COUNT(*) FROM entries", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(indexed, 0, "out-of-scope scan must not persist any rows");
+    }
+
+    #[test]
+    fn search_index_ignores_rows_outside_the_current_scope() {
+        let allowed = temp_root("search_allowed");
+        let outside = temp_root("search_outside");
+        std::fs::write(allowed.join("report.txt"), b"in scope").unwrap();
+        std::fs::write(outside.join("report.txt"), b"out of scope").unwrap();
+
+        let conn = open_test_db();
+        // Seed both an in-scope and an out-of-scope row directly, as if a
+        // previous scan ran under a wider scope that has since narrowed.
+        for dir in [&allowed, &outside] {
+            conn.execute(
+                "INSERT INTO entries (parent_id, path, name, size, mtime, is_dir, content_hash)
+                 VALUES (NULL, ?1, ?2, 0, 0, 0, NULL)",
+                rusqlite::params![dir.join("report.txt").to_string_lossy().to_string(), "report.txt"],
+            )
+            .unwrap();
+        }
+
+        let scope = ScopeState::new(vec![allowed.clone()]);
+        let results = search_index_inner("report", &conn, &scope).unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].path, allowed.join("report.txt").to_string_lossy().to_string());
+    }
+
+    #[test]
+    fn scan_dir_deletes_rows_for_files_removed_since_the_last_scan() {
+        let root = temp_root("reconcile");
+        std::fs::write(root.join("keep.txt"), b"keep").unwrap();
+        let removed = root.join("gone.txt");
+        std::fs::write(&removed, b"gone").unwrap();
+
+        let conn = open_test_db();
+        let scope = ScopeState::new(vec![root.clone()]);
+
+        scan_dir_inner(root.to_str().unwrap(), &conn, &scope).unwrap();
+        let count_for = |conn: &rusqlite::Connection, path: &std::path::Path| -> i64 {
+            conn.query_row(
+                "SELECT COUNT(*) FROM entries WHERE path = ?1",
+                [path.to_string_lossy().to_string()],
+                |row| row.get(0),
+            )
+            .unwrap()
+        };
+        assert_eq!(count_for(&conn, &removed), 1);
+
+        std::fs::remove_file(&removed).unwrap();
+        scan_dir_inner(root.to_str().unwrap(), &conn, &scope).unwrap();
+
+        assert_eq!(count_for(&conn, &removed), 0, "row for a deleted file must be reconciled away");
+        assert_eq!(count_for(&conn, &root.join("keep.txt")), 1, "untouched file must survive reconciliation");
+    }
+}
@@ -4,8 +4,12 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use std::fs;
-use std::path::PathBuf;
 use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+mod indexing;
+mod scope;
+mod watch;
 
 // File entry structure for directory listing
 #[derive(Serialize, Deserialize)]
@@ -15,6 +19,75 @@ pub struct FileEntry {
     pub is_dir: bool,
     pub size: u64,
     pub modified: u64,
+    pub created: u64,
+    pub accessed: u64,
+    pub is_symlink: bool,
+    // e.g. "0644 (rw-r--r--)" on Unix; `None` on platforms without POSIX permission bits
+    pub permissions: Option<String>,
+    // Number of entries inside this directory; `None` for files
+    pub child_count: Option<u64>,
+}
+
+fn system_time_to_secs(t: std::io::Result<std::time::SystemTime>) -> u64 {
+    t.ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(unix)]
+fn format_permissions(metadata: &fs::Metadata) -> Option<String> {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = metadata.permissions().mode();
+    let octal = mode & 0o777;
+
+    let bit = |shift: u32, ch: char| if mode & (1 << shift) != 0 { ch } else { '-' };
+    let rwx = format!(
+        "{}{}{}{}{}{}{}{}{}",
+        bit(8, 'r'),
+        bit(7, 'w'),
+        bit(6, 'x'),
+        bit(5, 'r'),
+        bit(4, 'w'),
+        bit(3, 'x'),
+        bit(2, 'r'),
+        bit(1, 'w'),
+        bit(0, 'x'),
+    );
+
+    Some(format!("{:04o} ({})", octal, rwx))
+}
+
+#[cfg(not(unix))]
+fn format_permissions(_metadata: &fs::Metadata) -> Option<String> {
+    None
+}
+
+fn count_children(path: &std::path::Path) -> Option<u64> {
+    fs::read_dir(path).ok().map(|rd| rd.flatten().count() as u64)
+}
+
+// Stat a single path into a `FileEntry`, shared by `read_directory` and the
+// indexing subsystem so both report identical fields for the same file.
+pub(crate) fn stat_entry(path: &std::path::Path) -> Option<FileEntry> {
+    let metadata = fs::symlink_metadata(path).ok()?;
+    let is_dir = metadata.is_dir();
+
+    Some(FileEntry {
+        name: path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string_lossy().to_string()),
+        path: path.to_string_lossy().to_string(),
+        is_dir,
+        size: metadata.len(),
+        modified: system_time_to_secs(metadata.modified()),
+        created: system_time_to_secs(metadata.created()),
+        accessed: system_time_to_secs(metadata.accessed()),
+        is_symlink: metadata.is_symlink(),
+        permissions: format_permissions(&metadata),
+        child_count: if is_dir { count_children(path) } else { None },
+    })
 }
 
 // Custom commands exposed to the frontend
@@ -33,31 +106,20 @@ fn get_system_info() -> serde_json::Value {
 }
 
 #[tauri::command]
-fn read_directory(path: String) -> Result<Vec<FileEntry>, String> {
-    let path = PathBuf::from(&path);
-    
+fn read_directory(path: String, scope: tauri::State<scope::ScopeState>) -> Result<Vec<FileEntry>, String> {
+    let path = scope::ensure_in_scope(&scope, &path).map_err(|e| e.to_string())?;
+
     if !path.exists() {
         return Err(format!("Path does not exist: {}", path.display()));
     }
-    
+
     let mut entries = Vec::new();
-    
+
     match fs::read_dir(&path) {
         Ok(read_dir) => {
             for entry in read_dir.flatten() {
-                if let Ok(metadata) = entry.metadata() {
-                    let modified = metadata
-                        .modified()
-                        .map(|t| t.duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs())
-                        .unwrap_or(0);
-                    
-                    entries.push(FileEntry {
-                        name: entry.file_name().to_string_lossy().to_string(),
-                        path: entry.path().to_string_lossy().to_string(),
-                        is_dir: metadata.is_dir(),
-                        size: metadata.len(),
-                        modified,
-                    });
+                if let Some(file_entry) = stat_entry(&entry.path()) {
+                    entries.push(file_entry);
                 }
             }
             // Sort: directories first, then by name
@@ -74,13 +136,104 @@ fn read_directory(path: String) -> Result<Vec<FileEntry>, String> {
     }
 }
 
+// Returned instead of a plain string error so the frontend can distinguish
+// "not valid UTF-8" from an actual I/O failure and fall back to
+// `read_file_bytes` instead of just showing an error toast.
+#[derive(Serialize)]
+#[serde(tag = "kind", content = "message")]
+pub enum ReadTextError {
+    NotUtf8,
+    Io(String),
+}
+
 #[tauri::command]
-fn read_file_content(path: String) -> Result<String, String> {
-    fs::read_to_string(&path).map_err(|e| format!("Failed to read file: {}", e))
+fn read_file_content(
+    path: String,
+    scope: tauri::State<scope::ScopeState>,
+) -> Result<String, ReadTextError> {
+    let path = scope::ensure_in_scope(&scope, &path).map_err(|e| ReadTextError::Io(e.to_string()))?;
+    let bytes = fs::read(&path).map_err(|e| ReadTextError::Io(format!("Failed to read file: {}", e)))?;
+    String::from_utf8(bytes).map_err(|_| ReadTextError::NotUtf8)
+}
+
+#[derive(Serialize)]
+pub struct FileChunk {
+    // Base64-encoded bytes of the requested window
+    pub data: String,
+    pub total_size: u64,
+    pub mime_type: String,
+}
+
+#[tauri::command]
+fn read_file_bytes(
+    path: String,
+    offset: u64,
+    length: u64,
+    scope: tauri::State<scope::ScopeState>,
+) -> Result<FileChunk, String> {
+    let path = scope::ensure_in_scope(&scope, &path).map_err(|e| e.to_string())?;
+    read_file_bytes_inner(&path, offset, length)
+}
+
+fn read_file_bytes_inner(path: &std::path::Path, offset: u64, length: u64) -> Result<FileChunk, String> {
+    use base64::Engine;
+    use std::io::{Read, Seek, SeekFrom};
+
+    let mut file = fs::File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+    let total_size = file
+        .metadata()
+        .map_err(|e| format!("Failed to read file metadata: {}", e))?
+        .len();
+
+    file.seek(SeekFrom::Start(offset))
+        .map_err(|e| format!("Failed to seek file: {}", e))?;
+    let capped_len = length.min(total_size.saturating_sub(offset));
+    let mut buf = vec![0u8; capped_len as usize];
+    let read = file.read(&mut buf).map_err(|e| format!("Failed to read file: {}", e))?;
+    buf.truncate(read);
+
+    Ok(FileChunk {
+        data: base64::engine::general_purpose::STANDARD.encode(&buf),
+        total_size,
+        mime_type: guess_mime_type(path),
+    })
+}
+
+fn guess_mime_type(path: &std::path::Path) -> String {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+
+    match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "svg" => "image/svg+xml",
+        "pdf" => "application/pdf",
+        "txt" | "md" => "text/plain",
+        "json" => "application/json",
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "js" | "mjs" => "text/javascript",
+        "mp4" | "mov" | "webm" => "video/mp4",
+        "mp3" | "wav" | "ogg" => "audio/mpeg",
+        "zip" => "application/zip",
+        _ => "application/octet-stream",
+    }
+    .to_string()
 }
 
 #[tauri::command]
-fn write_file_content(path: String, content: String) -> Result<(), String> {
+fn write_file_content(
+    path: String,
+    content: String,
+    scope: tauri::State<scope::ScopeState>,
+) -> Result<(), String> {
+    let path = scope::ensure_in_scope(&scope, &path).map_err(|e| e.to_string())?;
     fs::write(&path, &content).map_err(|e| format!("Failed to write file: {}", e))
 }
 
@@ -102,16 +255,75 @@ pub fn run() {
             get_system_info,
             read_directory,
             read_file_content,
+            read_file_bytes,
             write_file_content,
-            get_home_dir
+            get_home_dir,
+            indexing::scan_dir,
+            indexing::search_index,
+            indexing::find_duplicates,
+            scope::list_allowed_roots,
+            scope::add_allowed_root,
+            watch::watch_directory,
+            watch::unwatch_directory
         ])
-        .setup(|_app| {
+        .setup(|app| {
             #[cfg(debug_assertions)]
             {
                 // DevTools disabled to avoid unused variable warning
             }
+
+            let conn = indexing::init(app.handle()).map_err(std::io::Error::other)?;
+            app.manage(indexing::IndexState::new(conn));
+
+            // Scope to the home directory by default; callers can widen it
+            // at runtime via `add_allowed_root`.
+            let default_roots = dirs::home_dir().into_iter().collect();
+            app.manage(scope::ScopeState::new(default_roots));
+
+            app.manage(watch::WatchState::new());
+
             Ok(())
         })
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::Destroyed = event {
+                let app = window.app_handle();
+                if app.webview_windows().is_empty() {
+                    app.state::<watch::WatchState>().clear();
+                }
+            }
+        })
         .run(tauri::generate_context!())
         .expect("error while running SprintLoop");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+
+    fn temp_file(name: &str, contents: &[u8]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("sprintloop_lib_test_{}", name));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn read_file_bytes_clamps_length_to_remaining_bytes() {
+        let path = temp_file("clamp", b"hello world");
+
+        let chunk = read_file_bytes_inner(&path, 6, 1000).unwrap();
+
+        assert_eq!(chunk.total_size, 11);
+        assert_eq!(chunk.data, base64::engine::general_purpose::STANDARD.encode(b"world"));
+    }
+
+    #[test]
+    fn read_file_bytes_returns_empty_data_when_offset_is_past_eof() {
+        let path = temp_file("past_eof", b"hello");
+
+        let chunk = read_file_bytes_inner(&path, 100, 10).unwrap();
+
+        assert_eq!(chunk.total_size, 5);
+        assert!(chunk.data.is_empty());
+    }
+}
@@ -0,0 +1,209 @@
+// Restricts the filesystem commands to a configurable allowlist of root
+// directories, so the frontend can't read or write arbitrary paths on disk.
+// Mirrors Tauri's own capability/scope model, enforced here in our commands.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::AppHandle;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons};
+
+pub struct ScopeState(Mutex<Vec<PathBuf>>);
+
+impl ScopeState {
+    pub fn new(roots: Vec<PathBuf>) -> Self {
+        Self(Mutex::new(roots))
+    }
+}
+
+#[derive(Debug)]
+pub enum ScopeError {
+    OutsideScope(PathBuf),
+    Invalid(String),
+}
+
+impl std::fmt::Display for ScopeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScopeError::OutsideScope(path) => {
+                write!(f, "Path is outside the allowed scope: {}", path.display())
+            }
+            ScopeError::Invalid(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+// Canonicalizes `path` and checks it falls under one of the allowed roots,
+// catching both `..` traversal and symlink breakout. Returns the canonical
+// path so callers operate on the resolved location, not the raw input.
+//
+// `path` doesn't need to exist yet (e.g. a new file about to be written): in
+// that case only its parent directory is required to exist and be in scope.
+pub fn ensure_in_scope(state: &ScopeState, path: &str) -> Result<PathBuf, ScopeError> {
+    let requested = Path::new(path);
+
+    let canonical = if requested.exists() {
+        requested.canonicalize()
+    } else if requested.symlink_metadata().is_ok() {
+        // A dangling symlink can't be canonicalized, and writing through it
+        // would create its target wherever it points; reject it outright.
+        return Err(ScopeError::Invalid(format!("Path is a dangling symlink: {}", path)));
+    } else {
+        let parent = requested
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .ok_or_else(|| ScopeError::Invalid(format!("Path has no parent directory: {}", path)))?;
+        let file_name = requested
+            .file_name()
+            .ok_or_else(|| ScopeError::Invalid(format!("Path has no file name: {}", path)))?;
+        parent.canonicalize().map(|p| p.join(file_name))
+    }
+    .map_err(|e| ScopeError::Invalid(format!("Failed to resolve path: {}", e)))?;
+
+    let roots = state
+        .0
+        .lock()
+        .map_err(|_| ScopeError::Invalid("Scope lock poisoned".to_string()))?;
+
+    if is_under_any_root(&roots, &canonical) {
+        Ok(canonical)
+    } else {
+        Err(ScopeError::OutsideScope(canonical))
+    }
+}
+
+// Checks whether an already-existing path falls under one of the allowed
+// roots. Unlike `ensure_in_scope`, this never falls back to checking a
+// parent directory: it's used to filter rows that were indexed before, which
+// may reference paths that were removed, or roots that have since narrowed.
+pub fn path_in_scope(state: &ScopeState, path: &Path) -> bool {
+    let Ok(canonical) = path.canonicalize() else {
+        return false;
+    };
+    let Ok(roots) = state.0.lock() else {
+        return false;
+    };
+    is_under_any_root(&roots, &canonical)
+}
+
+fn is_under_any_root(roots: &[PathBuf], canonical: &Path) -> bool {
+    roots.iter().any(|root| {
+        root.canonicalize()
+            .map(|root| canonical.starts_with(&root))
+            .unwrap_or(false)
+    })
+}
+
+#[tauri::command]
+pub fn list_allowed_roots(state: tauri::State<ScopeState>) -> Result<Vec<String>, String> {
+    let roots = state.0.lock().map_err(|_| "Scope lock poisoned".to_string())?;
+    Ok(roots.iter().map(|p| p.to_string_lossy().to_string()).collect())
+}
+
+#[tauri::command]
+pub fn add_allowed_root(
+    path: String,
+    app: AppHandle,
+    state: tauri::State<ScopeState>,
+) -> Result<(), String> {
+    let canonical = Path::new(&path)
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve path: {}", e))?;
+
+    let already_allowed = {
+        let roots = state.0.lock().map_err(|_| "Scope lock poisoned".to_string())?;
+        is_under_any_root(&roots, &canonical)
+    };
+
+    // Widening the sandbox to a brand-new root needs explicit user consent —
+    // otherwise any code able to call this command could silently grant
+    // itself access to the whole disk, making `ensure_in_scope` decorative.
+    // A path already inside an allowed root needs no further confirmation.
+    if !already_allowed {
+        let confirmed = app
+            .dialog()
+            .message(format!(
+                "Allow SprintLoop to access files under:\n{}",
+                canonical.display()
+            ))
+            .title("Widen file access")
+            .buttons(MessageDialogButtons::OkCancel)
+            .blocking_show();
+
+        if !confirmed {
+            return Err(format!("User declined to widen scope to '{}'", canonical.display()));
+        }
+    }
+
+    let mut roots = state.0.lock().map_err(|_| "Scope lock poisoned".to_string())?;
+    if !roots.contains(&canonical) {
+        roots.push(canonical);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Each test gets its own subdirectory under the OS temp dir so parallel
+    // test runs don't see each other's files.
+    fn temp_root(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("sprintloop_scope_test_{}", name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).expect("create temp test root");
+        dir
+    }
+
+    #[test]
+    fn accepts_a_path_inside_an_allowed_root() {
+        let root = temp_root("accept");
+        let file = root.join("inside.txt");
+        std::fs::write(&file, b"ok").unwrap();
+
+        let state = ScopeState::new(vec![root.clone()]);
+        let resolved = ensure_in_scope(&state, file.to_str().unwrap()).unwrap();
+
+        assert_eq!(resolved, file.canonicalize().unwrap());
+    }
+
+    #[test]
+    fn rejects_traversal_outside_the_allowed_root() {
+        let root = temp_root("traverse_root");
+        let outside = temp_root("traverse_outside");
+        std::fs::write(outside.join("secret.txt"), b"nope").unwrap();
+
+        let state = ScopeState::new(vec![root.clone()]);
+        let escape = root
+            .join("..")
+            .join(outside.file_name().unwrap())
+            .join("secret.txt");
+
+        match ensure_in_scope(&state, escape.to_str().unwrap()) {
+            Err(ScopeError::OutsideScope(_)) => {}
+            other => panic!("expected OutsideScope, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn allows_a_not_yet_created_file_whose_parent_is_in_scope() {
+        let root = temp_root("new_file");
+        let state = ScopeState::new(vec![root.clone()]);
+
+        let resolved = ensure_in_scope(&state, root.join("new.txt").to_str().unwrap()).unwrap();
+
+        assert_eq!(resolved, root.canonicalize().unwrap().join("new.txt"));
+    }
+
+    #[test]
+    fn path_in_scope_reflects_the_current_allowlist() {
+        let root = temp_root("filter_root");
+        let outside = temp_root("filter_outside");
+        std::fs::write(root.join("a.txt"), b"a").unwrap();
+        std::fs::write(outside.join("b.txt"), b"b").unwrap();
+
+        let state = ScopeState::new(vec![root.clone()]);
+
+        assert!(path_in_scope(&state, &root.join("a.txt")));
+        assert!(!path_in_scope(&state, &outside.join("b.txt")));
+    }
+}
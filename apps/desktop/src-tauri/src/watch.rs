@@ -0,0 +1,135 @@
+// Streams filesystem change events to the frontend via a recursive `notify`
+// watcher, so the UI can update the listing from `read_directory`
+// incrementally instead of polling.
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+// Bursts within this window collapse into a single emitted event per path.
+const DEBOUNCE_WINDOW: Duration = Duration::from_millis(100);
+
+pub struct WatchState(Mutex<HashMap<String, RecommendedWatcher>>);
+
+impl WatchState {
+    pub fn new() -> Self {
+        Self(Mutex::new(HashMap::new()))
+    }
+
+    // Stops every active watcher; called when the last window closes.
+    pub fn clear(&self) {
+        if let Ok(mut watchers) = self.0.lock() {
+            watchers.clear();
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct ChangeEvent {
+    pub path: String,
+    pub kind: String,
+}
+
+fn event_kind_name(kind: &EventKind) -> &'static str {
+    match kind {
+        EventKind::Create(_) => "create",
+        EventKind::Modify(notify::event::ModifyKind::Name(_)) => "rename",
+        EventKind::Modify(_) => "modify",
+        EventKind::Remove(_) => "remove",
+        _ => "other",
+    }
+}
+
+#[tauri::command]
+pub fn watch_directory(
+    path: String,
+    app: AppHandle,
+    state: tauri::State<WatchState>,
+    scope: tauri::State<crate::scope::ScopeState>,
+) -> Result<(), String> {
+    let path = crate::scope::ensure_in_scope(&scope, &path)
+        .map_err(|e| e.to_string())?
+        .to_string_lossy()
+        .to_string();
+
+    let mut watchers = state.0.lock().map_err(|_| "Watcher registry lock poisoned".to_string())?;
+    if watchers.contains_key(&path) {
+        return Ok(());
+    }
+
+    let (tx, rx) = mpsc::channel::<notify::Result<Event>>();
+    let mut watcher =
+        notify::recommended_watcher(tx).map_err(|e| format!("Failed to create watcher: {}", e))?;
+    watcher
+        .watch(Path::new(&path), RecursiveMode::Recursive)
+        .map_err(|e| format!("Failed to watch path: {}", e))?;
+
+    let event_app = app.clone();
+    std::thread::spawn(move || {
+        // Remember the latest event kind per path and flush the batch once
+        // `DEBOUNCE_WINDOW` passes since the *oldest* unflushed event — not
+        // since the last-received one. A plain quiet-period debounce would
+        // never flush under a sustained burst (a big copy, `git checkout`, a
+        // build writing output continuously); anchoring the deadline to the
+        // oldest pending event instead gives a hard ~`DEBOUNCE_WINDOW`
+        // ceiling even while events keep arriving. The loop (and thread) ends
+        // on its own once `watcher` is dropped and disconnects `rx`.
+        let mut pending: HashMap<PathBuf, &'static str> = HashMap::new();
+        let mut oldest_pending: Option<std::time::Instant> = None;
+        loop {
+            let timeout = match oldest_pending {
+                Some(since) => DEBOUNCE_WINDOW.saturating_sub(since.elapsed()),
+                None => DEBOUNCE_WINDOW,
+            };
+
+            match rx.recv_timeout(timeout) {
+                Ok(Ok(event)) => {
+                    let kind = event_kind_name(&event.kind);
+                    for path in event.paths {
+                        pending.insert(path, kind);
+                    }
+                    oldest_pending.get_or_insert_with(std::time::Instant::now);
+                }
+                Ok(Err(_)) => {}
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    for (path, kind) in pending.drain() {
+                        let _ = event_app.emit(
+                            "fs-change",
+                            ChangeEvent {
+                                path: path.to_string_lossy().to_string(),
+                                kind: kind.to_string(),
+                            },
+                        );
+                    }
+                    oldest_pending = None;
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    });
+
+    watchers.insert(path, watcher);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn unwatch_directory(
+    path: String,
+    state: tauri::State<WatchState>,
+    scope: tauri::State<crate::scope::ScopeState>,
+) -> Result<(), String> {
+    // Resolve the same way `watch_directory` did so the lookup key matches.
+    let path = crate::scope::ensure_in_scope(&scope, &path)
+        .map_err(|e| e.to_string())?
+        .to_string_lossy()
+        .to_string();
+    let mut watchers = state.0.lock().map_err(|_| "Watcher registry lock poisoned".to_string())?;
+    // Dropping the watcher stops the OS-level watch and its debounce thread.
+    watchers.remove(&path);
+    Ok(())
+}